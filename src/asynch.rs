@@ -0,0 +1,169 @@
+//! Async driver variant for use with [`embedded_hal_async::spi::SpiDevice`].
+
+use embedded_hal_async::spi::SpiDevice;
+
+use crate::{color_to_array, gamma_lut, identity_lut, DEFAULT_GAMMA, FRAME_END, FRAME_START};
+
+/// Struct representing a P9813 controller driven over an async SPI peripheral.
+#[derive(Debug)]
+pub struct AsyncP9813<SPI> {
+  spi: SPI,
+  lut: [u8; 256],
+  brightness: u8,
+}
+
+impl<SPI> AsyncP9813<SPI> {
+  /// Create a new `AsyncP9813` with the given SPI peripheral.
+  ///
+  /// Channel values are gamma-corrected with a default gamma of `2.2`
+  /// before being sent to the chip, just like [`P9813::new`]. Use
+  /// [`AsyncP9813::from_raw`] to send channel values unmodified.
+  ///
+  /// [`P9813::new`]: crate::P9813::new
+  pub fn new(spi: SPI) -> Self {
+    AsyncP9813 { spi, lut: gamma_lut(DEFAULT_GAMMA), brightness: u8::MAX }
+  }
+
+  /// Create a new `AsyncP9813` with the given SPI peripheral, sending
+  /// channel values unmodified instead of gamma-correcting them.
+  pub const fn from_raw(spi: SPI) -> Self {
+    AsyncP9813 { spi, lut: identity_lut(), brightness: u8::MAX }
+  }
+
+  /// Replace the gamma correction table with one built from `gamma`.
+  pub fn with_gamma(mut self, gamma: f32) -> Self {
+    self.lut = gamma_lut(gamma);
+    self
+  }
+
+  /// Scale every channel by a global brightness `level`, where `0` is off
+  /// and `255` is full brightness.
+  ///
+  /// The checksum prefix is recomputed from the brightness-adjusted channel
+  /// values, not the raw input values, to keep the chip's checksum valid:
+  ///
+  /// ```
+  /// # fn main() -> Result<(), embedded_hal::spi::ErrorKind> {
+  /// # use embedded_hal_mock::eh1::{spi::{Mock as SpiMock, Transaction as SpiTransaction}, delay::NoopDelay};
+  /// # let spi = SpiMock::new(&[
+  /// #   // Start frame.
+  /// #   SpiTransaction::transaction_start(),
+  /// #   SpiTransaction::write_vec(vec![0x00, 0x00, 0x00, 0x00]),
+  /// #   SpiTransaction::transaction_end(),
+  /// #
+  /// #   // Set color, halved brightness.
+  /// #   SpiTransaction::transaction_start(),
+  /// #   SpiTransaction::write_vec(vec![0b11111101, 0, 0, 128]),
+  /// #   SpiTransaction::transaction_end(),
+  /// #
+  /// #   // End frame.
+  /// #   SpiTransaction::transaction_start(),
+  /// #   SpiTransaction::write_vec(vec![0x00, 0x00, 0x00, 0x00]),
+  /// #   SpiTransaction::transaction_end(),
+  /// # ]);
+  /// # use p9813::AsyncP9813;
+  /// let mut p9813 = AsyncP9813::from_raw(spi).with_brightness(128);
+  /// pollster::block_on(p9813.set_color(255, 0, 0))?;
+  /// # let mut spi = p9813.release();
+  /// # spi.done();
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub const fn with_brightness(mut self, level: u8) -> Self {
+    self.brightness = level;
+    self
+  }
+
+  /// Release the contained SPI peripheral.
+  pub fn release(self) -> SPI {
+    self.spi
+  }
+
+  /// Apply the gamma correction table and brightness level to a single channel.
+  fn scale(&self, channel: u8) -> u8 {
+    (self.lut[channel as usize] as u16 * self.brightness as u16 / 255) as u8
+  }
+}
+
+impl<SPI: SpiDevice<u8>> AsyncP9813<SPI> {
+  /// Set color for a single P9813.
+  ///
+  /// ```
+  /// # fn main() -> Result<(), embedded_hal::spi::ErrorKind> {
+  /// # use embedded_hal_mock::eh1::{spi::{Mock as SpiMock, Transaction as SpiTransaction}, delay::NoopDelay};
+  /// # let spi = SpiMock::new(&[
+  /// #   // Start frame.
+  /// #   SpiTransaction::transaction_start(),
+  /// #   SpiTransaction::write_vec(vec![0x00, 0x00, 0x00, 0x00]),
+  /// #   SpiTransaction::transaction_end(),
+  /// #
+  /// #   // Set color.
+  /// #   SpiTransaction::transaction_start(),
+  /// #   SpiTransaction::write_vec(vec![0b11000011, 200, 255, 0]),
+  /// #   SpiTransaction::transaction_end(),
+  /// #
+  /// #   // End frame.
+  /// #   SpiTransaction::transaction_start(),
+  /// #   SpiTransaction::write_vec(vec![0x00, 0x00, 0x00, 0x00]),
+  /// #   SpiTransaction::transaction_end(),
+  /// # ]);
+  /// # use p9813::AsyncP9813;
+  /// let mut p9813 = AsyncP9813::from_raw(spi);
+  /// pollster::block_on(p9813.set_color(0, 255, 200))?;
+  /// # let mut spi = p9813.release();
+  /// # spi.done();
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn set_color(&mut self, r: u8, g: u8, b: u8) -> Result<(), SPI::Error> {
+    self.set_colors([(r, g, b)]).await
+  }
+
+  /// Set colors for multiple P9813s chained together.
+  ///
+  /// ```
+  /// # fn main() -> Result<(), embedded_hal::spi::ErrorKind> {
+  /// # use embedded_hal_mock::eh1::{spi::{Mock as SpiMock, Transaction as SpiTransaction}, delay::NoopDelay};
+  /// # let spi = SpiMock::new(&[
+  /// #   // Start frame.
+  /// #   SpiTransaction::transaction_start(),
+  /// #   SpiTransaction::write_vec(vec![0x00, 0x00, 0x00, 0x00]),
+  /// #   SpiTransaction::transaction_end(),
+  /// #
+  /// #   // Set color.
+  /// #   SpiTransaction::transaction_start(),
+  /// #   SpiTransaction::write_vec(vec![0b11000011, 200, 255, 0]),
+  /// #   SpiTransaction::transaction_end(),
+  /// #
+  /// #   // Set color.
+  /// #   SpiTransaction::transaction_start(),
+  /// #   SpiTransaction::write_vec(vec![0b11111100, 20, 50, 255]),
+  /// #   SpiTransaction::transaction_end(),
+  /// #
+  /// #   // End frame.
+  /// #   SpiTransaction::transaction_start(),
+  /// #   SpiTransaction::write_vec(vec![0x00, 0x00, 0x00, 0x00]),
+  /// #   SpiTransaction::transaction_end(),
+  /// # ]);
+  /// # use p9813::AsyncP9813;
+  /// let mut p9813 = AsyncP9813::from_raw(spi);
+  /// pollster::block_on(p9813.set_colors([(0, 255, 200), (255, 50, 20)]))?;
+  /// # let mut spi = p9813.release();
+  /// # spi.done();
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn set_colors(&mut self, colors: impl AsRef<[(u8, u8, u8)]>) -> Result<(), SPI::Error> {
+    self.spi.write(&FRAME_START).await?;
+
+    for &(r, g, b) in colors.as_ref().iter() {
+      let r = self.scale(r);
+      let g = self.scale(g);
+      let b = self.scale(b);
+
+      self.spi.write(&color_to_array(r, g, b)).await?;
+    }
+
+    self.spi.write(&FRAME_END).await
+  }
+}
@@ -0,0 +1,202 @@
+//! Compatibility impl for HALs that only implement `embedded-hal` 0.2's
+//! blocking [`eh02::blocking::spi::Write`] trait, for ecosystem crates that
+//! have not yet migrated to `embedded-hal` 1.0's [`embedded_hal::spi::SpiDevice`].
+//!
+//! These methods are suffixed with `_eh02` so they can coexist with the
+//! `embedded-hal` 1.0 methods of the same [`P9813`] without ambiguity, in
+//! case a peripheral happens to implement both traits.
+
+use eh02::blocking::spi::Write;
+
+use crate::{color_to_array, BufferedError, P9813, FRAME_END, FRAME_START};
+
+impl<SPI: Write<u8>> P9813<SPI> {
+  /// Set color for a single P9813, using an `embedded-hal` 0.2 SPI peripheral.
+  ///
+  /// See [`P9813::set_color`] for the `embedded-hal` 1.0 equivalent.
+  ///
+  /// ```
+  /// # fn main() -> Result<(), embedded_hal_mock::eh0::MockError> {
+  /// # use embedded_hal_mock::eh0::spi::{Mock as SpiMock, Transaction as SpiTransaction};
+  /// # let mut spi = SpiMock::new(&[
+  /// #   // Start frame.
+  /// #   SpiTransaction::write(vec![0x00, 0x00, 0x00, 0x00]),
+  /// #   // Set color.
+  /// #   SpiTransaction::write(vec![0b11000011, 200, 255, 0]),
+  /// #   // End frame.
+  /// #   SpiTransaction::write(vec![0x00, 0x00, 0x00, 0x00]),
+  /// # ]);
+  /// # use p9813::P9813;
+  /// let mut p9813 = P9813::from_raw(spi);
+  /// p9813.set_color_eh02(0, 255, 200)?;
+  /// # let mut spi = p9813.release();
+  /// # spi.done();
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn set_color_eh02(&mut self, r: u8, g: u8, b: u8) -> Result<(), SPI::Error> {
+    self.set_colors_eh02([(r, g, b)])
+  }
+
+  /// Set colors for multiple P9813s chained together, using an
+  /// `embedded-hal` 0.2 SPI peripheral.
+  ///
+  /// See [`P9813::set_colors`] for the `embedded-hal` 1.0 equivalent.
+  ///
+  /// ```
+  /// # fn main() -> Result<(), embedded_hal_mock::eh0::MockError> {
+  /// # use embedded_hal_mock::eh0::spi::{Mock as SpiMock, Transaction as SpiTransaction};
+  /// # let mut spi = SpiMock::new(&[
+  /// #   // Start frame.
+  /// #   SpiTransaction::write(vec![0x00, 0x00, 0x00, 0x00]),
+  /// #   // Set color.
+  /// #   SpiTransaction::write(vec![0b11000011, 200, 255, 0]),
+  /// #   // Set color.
+  /// #   SpiTransaction::write(vec![0b11111100, 20, 50, 255]),
+  /// #   // End frame.
+  /// #   SpiTransaction::write(vec![0x00, 0x00, 0x00, 0x00]),
+  /// # ]);
+  /// # use p9813::P9813;
+  /// let mut p9813 = P9813::from_raw(spi);
+  /// p9813.set_colors_eh02([(0, 255, 200), (255, 50, 20)])?;
+  /// # let mut spi = p9813.release();
+  /// # spi.done();
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn set_colors_eh02(&mut self, colors: impl AsRef<[(u8, u8, u8)]>) -> Result<(), SPI::Error> {
+    self.spi.write(&FRAME_START)?;
+
+    for &(r, g, b) in colors.as_ref().iter() {
+      let r = self.scale(r);
+      let g = self.scale(g);
+      let b = self.scale(b);
+
+      self.spi.write(&color_to_array(r, g, b))?;
+    }
+
+    self.spi.write(&FRAME_END)
+  }
+
+  /// Like [`P9813::set_colors_eh02`], but assembles the whole frame into a
+  /// stack-allocated buffer of `LEN` bytes and sends it in a single SPI
+  /// transaction.
+  ///
+  /// See [`P9813::set_colors_buffered`] for the `embedded-hal` 1.0 equivalent.
+  ///
+  /// ```
+  /// # fn main() -> Result<(), p9813::BufferedError<embedded_hal_mock::eh0::MockError>> {
+  /// # use embedded_hal_mock::eh0::spi::{Mock as SpiMock, Transaction as SpiTransaction};
+  /// # let mut spi = SpiMock::new(&[
+  /// #   SpiTransaction::write(vec![
+  /// #     // Start frame.
+  /// #     0x00, 0x00, 0x00, 0x00,
+  /// #     // Set color.
+  /// #     0b11000011, 200, 255, 0,
+  /// #     // Set color.
+  /// #     0b11111100, 20, 50, 255,
+  /// #     // End frame.
+  /// #     0x00, 0x00, 0x00, 0x00,
+  /// #   ]),
+  /// # ]);
+  /// # use p9813::P9813;
+  /// let mut p9813 = P9813::from_raw(spi);
+  /// p9813.set_colors_buffered_eh02::<16>([(0, 255, 200), (255, 50, 20)])?;
+  /// # let mut spi = p9813.release();
+  /// # spi.done();
+  /// # Ok(())
+  /// # }
+  /// ```
+  ///
+  /// Passing a buffer too small for the frame returns
+  /// [`BufferedError::BufferTooSmall`] instead of sending anything:
+  ///
+  /// ```
+  /// # use embedded_hal_mock::eh0::spi::Mock as SpiMock;
+  /// # let spi = SpiMock::new(&[]);
+  /// use p9813::{BufferedError, P9813};
+  ///
+  /// let mut p9813 = P9813::from_raw(spi);
+  /// let result = p9813.set_colors_buffered_eh02::<15>([(0, 255, 200), (255, 50, 20)]);
+  /// assert!(matches!(result, Err(BufferedError::BufferTooSmall)));
+  /// # let mut spi = p9813.release();
+  /// # spi.done();
+  /// ```
+  pub fn set_colors_buffered_eh02<const LEN: usize>(
+    &mut self,
+    colors: impl AsRef<[(u8, u8, u8)]>,
+  ) -> Result<(), BufferedError<SPI::Error>> {
+    let colors = colors.as_ref();
+    let len = 4 * (colors.len() + 2);
+
+    if len > LEN {
+      return Err(BufferedError::BufferTooSmall);
+    }
+
+    let mut buffer = [0; LEN];
+    buffer[..4].copy_from_slice(&FRAME_START);
+
+    for (i, &(r, g, b)) in colors.iter().enumerate() {
+      let r = self.scale(r);
+      let g = self.scale(g);
+      let b = self.scale(b);
+
+      let offset = 4 * (i + 1);
+      buffer[offset..offset + 4].copy_from_slice(&color_to_array(r, g, b));
+    }
+
+    buffer[len - 4..len].copy_from_slice(&FRAME_END);
+
+    self.spi.write(&buffer[..len])?;
+
+    Ok(())
+  }
+
+  /// Like [`P9813::set_colors_buffered_eh02`], but assembles the frame into
+  /// a heap-allocated buffer instead of a stack-allocated one.
+  ///
+  /// See [`P9813::set_colors_alloc`] for the `embedded-hal` 1.0 equivalent.
+  ///
+  /// ```
+  /// # fn main() -> Result<(), embedded_hal_mock::eh0::MockError> {
+  /// # use embedded_hal_mock::eh0::spi::{Mock as SpiMock, Transaction as SpiTransaction};
+  /// # let mut spi = SpiMock::new(&[
+  /// #   SpiTransaction::write(vec![
+  /// #     // Start frame.
+  /// #     0x00, 0x00, 0x00, 0x00,
+  /// #     // Set color.
+  /// #     0b11000011, 200, 255, 0,
+  /// #     // Set color.
+  /// #     0b11111100, 20, 50, 255,
+  /// #     // End frame.
+  /// #     0x00, 0x00, 0x00, 0x00,
+  /// #   ]),
+  /// # ]);
+  /// # use p9813::P9813;
+  /// let mut p9813 = P9813::from_raw(spi);
+  /// p9813.set_colors_alloc_eh02([(0, 255, 200), (255, 50, 20)])?;
+  /// # let mut spi = p9813.release();
+  /// # spi.done();
+  /// # Ok(())
+  /// # }
+  /// ```
+  #[cfg(feature = "alloc")]
+  pub fn set_colors_alloc_eh02(&mut self, colors: impl AsRef<[(u8, u8, u8)]>) -> Result<(), SPI::Error> {
+    let colors = colors.as_ref();
+    let mut buffer = alloc::vec::Vec::with_capacity(4 * (colors.len() + 2));
+
+    buffer.extend_from_slice(&FRAME_START);
+
+    for &(r, g, b) in colors.iter() {
+      let r = self.scale(r);
+      let g = self.scale(g);
+      let b = self.scale(b);
+
+      buffer.extend_from_slice(&color_to_array(r, g, b));
+    }
+
+    buffer.extend_from_slice(&FRAME_END);
+
+    self.spi.write(&buffer)
+  }
+}
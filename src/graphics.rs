@@ -0,0 +1,114 @@
+//! `embedded-graphics` integration for driving a P9813 chain as a 1-pixel-tall framebuffer.
+
+use embedded_graphics::{
+  draw_target::DrawTarget,
+  geometry::{OriginDimensions, Size},
+  pixelcolor::{Rgb888, RgbColor},
+  Pixel,
+};
+use embedded_hal::spi::SpiDevice;
+use heapless::Vec;
+
+use crate::P9813;
+
+/// An `embedded-graphics` [`DrawTarget`] backed by a chain of `N` P9813 controllers.
+///
+/// Pixels are buffered until [`Strip::flush`] is called, which forwards the
+/// whole buffer to the wrapped [`P9813`] in a single [`P9813::set_colors`] call.
+#[derive(Debug)]
+pub struct Strip<SPI, const N: usize> {
+  p9813: P9813<SPI>,
+  buffer: Vec<(u8, u8, u8), N>,
+}
+
+impl<SPI: SpiDevice<u8>, const N: usize> Strip<SPI, N> {
+  /// Create a new `Strip` wrapping the given `P9813` controller, with all
+  /// pixels initially off.
+  pub fn new(p9813: P9813<SPI>) -> Self {
+    let mut buffer = Vec::new();
+    buffer.resize(N, (0, 0, 0)).ok();
+
+    Strip { p9813, buffer }
+  }
+
+  /// Send the buffered pixels to the chain.
+  ///
+  /// ```
+  /// # fn main() -> Result<(), embedded_hal::spi::ErrorKind> {
+  /// # use embedded_hal_mock::eh1::{spi::{Mock as SpiMock, Transaction as SpiTransaction}, delay::NoopDelay};
+  /// # let spi = SpiMock::new(&[
+  /// #   // Start frame.
+  /// #   SpiTransaction::transaction_start(),
+  /// #   SpiTransaction::write_vec(vec![0x00, 0x00, 0x00, 0x00]),
+  /// #   SpiTransaction::transaction_end(),
+  /// #
+  /// #   // Set color.
+  /// #   SpiTransaction::transaction_start(),
+  /// #   SpiTransaction::write_vec(vec![0b11000011, 200, 255, 0]),
+  /// #   SpiTransaction::transaction_end(),
+  /// #
+  /// #   // Set color.
+  /// #   SpiTransaction::transaction_start(),
+  /// #   SpiTransaction::write_vec(vec![0b11111100, 20, 50, 255]),
+  /// #   SpiTransaction::transaction_end(),
+  /// #
+  /// #   // End frame.
+  /// #   SpiTransaction::transaction_start(),
+  /// #   SpiTransaction::write_vec(vec![0x00, 0x00, 0x00, 0x00]),
+  /// #   SpiTransaction::transaction_end(),
+  /// # ]);
+  /// use embedded_graphics::{pixelcolor::Rgb888, prelude::*, Pixel};
+  /// use p9813::{P9813, Strip};
+  ///
+  /// let mut strip: Strip<_, 2> = Strip::new(P9813::from_raw(spi));
+  ///
+  /// // `draw_iter` only buffers pixels; out-of-range points are ignored.
+  /// strip
+  ///   .draw_iter([
+  ///     Pixel(Point::new(0, 0), Rgb888::new(0, 255, 200)),
+  ///     Pixel(Point::new(1, 0), Rgb888::new(255, 50, 20)),
+  ///     Pixel(Point::new(2, 0), Rgb888::new(1, 2, 3)),
+  ///   ])
+  ///   .unwrap();
+  ///
+  /// strip.flush()?;
+  /// # let mut spi = strip.release().release();
+  /// # spi.done();
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn flush(&mut self) -> Result<(), SPI::Error> {
+    self.p9813.set_colors(self.buffer.as_slice())
+  }
+
+  /// Release the contained `P9813` controller.
+  pub fn release(self) -> P9813<SPI> {
+    self.p9813
+  }
+}
+
+impl<SPI, const N: usize> OriginDimensions for Strip<SPI, N> {
+  fn size(&self) -> Size {
+    Size::new(N as u32, 1)
+  }
+}
+
+impl<SPI: SpiDevice<u8>, const N: usize> DrawTarget for Strip<SPI, N> {
+  type Color = Rgb888;
+  type Error = core::convert::Infallible;
+
+  fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+  where
+    I: IntoIterator<Item = Pixel<Self::Color>>,
+  {
+    for Pixel(point, color) in pixels {
+      if point.y != 0 || point.x < 0 || point.x as usize >= N {
+        continue;
+      }
+
+      self.buffer[point.x as usize] = (color.r(), color.g(), color.b());
+    }
+
+    Ok(())
+  }
+}
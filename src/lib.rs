@@ -3,6 +3,22 @@
 
 //! A library for the P9813 RGB controller.
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "async")]
+mod asynch;
+#[cfg(feature = "async")]
+pub use asynch::AsyncP9813;
+
+#[cfg(feature = "embedded-graphics")]
+mod graphics;
+#[cfg(feature = "embedded-graphics")]
+pub use graphics::Strip;
+
+#[cfg(feature = "eh02")]
+mod eh02;
+
 use embedded_hal::spi::SpiDevice;
 
 const FLAG_BITS: u8 = 0b11_00_00_00;
@@ -27,10 +43,37 @@ fn color_to_array(r: u8, g: u8, b: u8) -> [u8; 4] {
   [prefix, b, g, r]
 }
 
+/// Default gamma applied by [`P9813::new`].
+const DEFAULT_GAMMA: f32 = 2.2;
+
+const fn identity_lut() -> [u8; 256] {
+  let mut lut = [0; 256];
+
+  let mut i = 0;
+  while i < lut.len() {
+    lut[i] = i as u8;
+    i += 1;
+  }
+
+  lut
+}
+
+fn gamma_lut(gamma: f32) -> [u8; 256] {
+  let mut lut = [0; 256];
+
+  for (i, entry) in lut.iter_mut().enumerate() {
+    *entry = libm::roundf(libm::powf(i as f32 / 255.0, gamma) * 255.0) as u8;
+  }
+
+  lut
+}
+
 /// Struct representing a P9813 controller.
 #[derive(Debug)]
 pub struct P9813<SPI> {
   spi: SPI,
+  lut: [u8; 256],
+  brightness: u8,
 }
 
 impl P9813<()> {
@@ -38,10 +81,73 @@ impl P9813<()> {
   pub const MAX_CLOCK_FREQUENCY: u32 = 15_000_000;
 }
 
-impl<SPI: SpiDevice<u8>> P9813<SPI> {
+impl<SPI> P9813<SPI> {
   /// Create a new `P9813` with the given SPI peripheral.
-  pub const fn new(spi: SPI) -> Self {
-    P9813 { spi }
+  ///
+  /// Channel values are gamma-corrected with a default gamma of `2.2`
+  /// before being sent to the chip, since the P9813 has no hardware
+  /// brightness or gamma correction of its own. Use [`P9813::from_raw`]
+  /// to send channel values unmodified, or [`P9813::with_gamma`] to pick
+  /// a different gamma.
+  ///
+  /// Note that, unlike in earlier versions of this crate, this is no
+  /// longer a `const fn`, since building the gamma-correction table
+  /// requires the non-const [`libm::powf`]. Use [`P9813::from_raw`] in a
+  /// `const` context.
+  pub fn new(spi: SPI) -> Self {
+    P9813 { spi, lut: gamma_lut(DEFAULT_GAMMA), brightness: u8::MAX }
+  }
+
+  /// Create a new `P9813` with the given SPI peripheral, sending channel
+  /// values unmodified instead of gamma-correcting them.
+  pub const fn from_raw(spi: SPI) -> Self {
+    P9813 { spi, lut: identity_lut(), brightness: u8::MAX }
+  }
+
+  /// Replace the gamma correction table with one built from `gamma`.
+  pub fn with_gamma(mut self, gamma: f32) -> Self {
+    self.lut = gamma_lut(gamma);
+    self
+  }
+
+  /// Scale every channel by a global brightness `level`, where `0` is off
+  /// and `255` is full brightness.
+  ///
+  /// The flag-bit prefix is recomputed from the brightness- and
+  /// gamma-adjusted channel values, not the original ones passed to
+  /// [`P9813::set_color`], so the chip's checksum nibbles stay valid.
+  ///
+  /// ```
+  /// # fn main() -> Result<(), embedded_hal::spi::ErrorKind> {
+  /// # use embedded_hal_mock::eh1::{spi::{Mock as SpiMock, Transaction as SpiTransaction}, delay::NoopDelay};
+  /// # let spi = SpiMock::new(&[
+  /// #   // Start frame.
+  /// #   SpiTransaction::transaction_start(),
+  /// #   SpiTransaction::write_vec(vec![0x00, 0x00, 0x00, 0x00]),
+  /// #   SpiTransaction::transaction_end(),
+  /// #
+  /// #   // Set color, scaled to half brightness: 255 -> 128, with the
+  /// #   // flag-bit prefix recomputed from the scaled value.
+  /// #   SpiTransaction::transaction_start(),
+  /// #   SpiTransaction::write_vec(vec![0b11111101, 0, 0, 128]),
+  /// #   SpiTransaction::transaction_end(),
+  /// #
+  /// #   // End frame.
+  /// #   SpiTransaction::transaction_start(),
+  /// #   SpiTransaction::write_vec(vec![0x00, 0x00, 0x00, 0x00]),
+  /// #   SpiTransaction::transaction_end(),
+  /// # ]);
+  /// # use p9813::P9813;
+  /// let mut p9813 = P9813::from_raw(spi).with_brightness(128);
+  /// p9813.set_color(255, 0, 0)?;
+  /// # let mut spi = p9813.release();
+  /// # spi.done();
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub const fn with_brightness(mut self, level: u8) -> Self {
+    self.brightness = level;
+    self
   }
 
   /// Release the contained SPI peripheral.
@@ -49,6 +155,13 @@ impl<SPI: SpiDevice<u8>> P9813<SPI> {
     self.spi
   }
 
+  /// Apply the gamma correction table and brightness level to a single channel.
+  fn scale(&self, channel: u8) -> u8 {
+    (self.lut[channel as usize] as u16 * self.brightness as u16 / 255) as u8
+  }
+}
+
+impl<SPI: SpiDevice<u8>> P9813<SPI> {
   /// Set color for a single P9813.
   ///
   /// ```
@@ -71,7 +184,7 @@ impl<SPI: SpiDevice<u8>> P9813<SPI> {
   /// #   SpiTransaction::transaction_end(),
   /// # ]);
   /// # use p9813::P9813;
-  /// let mut p9813 = P9813::new(spi);
+  /// let mut p9813 = P9813::from_raw(spi);
   /// p9813.set_color(0, 255, 200)?;
   /// # let mut spi = p9813.release();
   /// # spi.done();
@@ -109,7 +222,7 @@ impl<SPI: SpiDevice<u8>> P9813<SPI> {
   /// #   SpiTransaction::transaction_end(),
   /// # ]);
   /// # use p9813::P9813;
-  /// let mut p9813 = P9813::new(spi);
+  /// let mut p9813 = P9813::from_raw(spi);
   /// p9813.set_colors([(0, 255, 200), (255, 50, 20)])?;
   /// # let mut spi = p9813.release();
   /// # spi.done();
@@ -120,9 +233,149 @@ impl<SPI: SpiDevice<u8>> P9813<SPI> {
     self.spi.write(&FRAME_START)?;
 
     for &(r, g, b) in colors.as_ref().iter() {
+      let r = self.scale(r);
+      let g = self.scale(g);
+      let b = self.scale(b);
+
       self.spi.write(&color_to_array(r, g, b))?;
     }
 
     self.spi.write(&FRAME_END)
   }
+
+  /// Like [`P9813::set_colors`], but assembles the whole frame (start frame,
+  /// every color, end frame) into a stack-allocated buffer of `LEN` bytes and
+  /// sends it in a single SPI transaction, instead of one transaction per frame.
+  ///
+  /// `LEN` must be at least `4 * (colors.len() + 2)`, or
+  /// [`BufferedError::BufferTooSmall`] is returned.
+  ///
+  /// ```
+  /// # fn main() -> Result<(), p9813::BufferedError<embedded_hal::spi::ErrorKind>> {
+  /// # use embedded_hal_mock::eh1::{spi::{Mock as SpiMock, Transaction as SpiTransaction}, delay::NoopDelay};
+  /// # let spi = SpiMock::new(&[
+  /// #   // Start frame, both colors and end frame, all in one transaction.
+  /// #   SpiTransaction::transaction_start(),
+  /// #   SpiTransaction::write_vec(vec![
+  /// #     0x00, 0x00, 0x00, 0x00,
+  /// #     0b11000011, 200, 255, 0,
+  /// #     0b11111100, 20, 50, 255,
+  /// #     0x00, 0x00, 0x00, 0x00,
+  /// #   ]),
+  /// #   SpiTransaction::transaction_end(),
+  /// # ]);
+  /// # use p9813::P9813;
+  /// let mut p9813 = P9813::from_raw(spi);
+  /// p9813.set_colors_buffered::<16>([(0, 255, 200), (255, 50, 20)])?;
+  /// # let mut spi = p9813.release();
+  /// # spi.done();
+  /// # Ok(())
+  /// # }
+  /// ```
+  ///
+  /// A `LEN` too small for the whole frame is rejected before any SPI
+  /// transaction is attempted:
+  ///
+  /// ```
+  /// # use embedded_hal_mock::eh1::spi::Mock as SpiMock;
+  /// use p9813::{BufferedError, P9813};
+  ///
+  /// let spi = SpiMock::<u8>::new(&[]);
+  /// let mut p9813 = P9813::from_raw(spi);
+  ///
+  /// let result = p9813.set_colors_buffered::<15>([(0, 255, 200), (255, 50, 20)]);
+  /// assert!(matches!(result, Err(BufferedError::BufferTooSmall)));
+  /// # let mut spi = p9813.release();
+  /// # spi.done();
+  /// ```
+  pub fn set_colors_buffered<const LEN: usize>(
+    &mut self,
+    colors: impl AsRef<[(u8, u8, u8)]>,
+  ) -> Result<(), BufferedError<SPI::Error>> {
+    let colors = colors.as_ref();
+    let len = 4 * (colors.len() + 2);
+
+    if len > LEN {
+      return Err(BufferedError::BufferTooSmall);
+    }
+
+    let mut buffer = [0; LEN];
+    buffer[..4].copy_from_slice(&FRAME_START);
+
+    for (i, &(r, g, b)) in colors.iter().enumerate() {
+      let r = self.scale(r);
+      let g = self.scale(g);
+      let b = self.scale(b);
+
+      let offset = 4 * (i + 1);
+      buffer[offset..offset + 4].copy_from_slice(&color_to_array(r, g, b));
+    }
+
+    buffer[len - 4..len].copy_from_slice(&FRAME_END);
+
+    self.spi.write(&buffer[..len])?;
+
+    Ok(())
+  }
+
+  /// Like [`P9813::set_colors_buffered`], but assembles the frame into a
+  /// heap-allocated buffer instead of a stack-allocated one, so no `LEN`
+  /// needs to be chosen up front.
+  ///
+  /// ```
+  /// # fn main() -> Result<(), embedded_hal::spi::ErrorKind> {
+  /// # use embedded_hal_mock::eh1::{spi::{Mock as SpiMock, Transaction as SpiTransaction}, delay::NoopDelay};
+  /// # let spi = SpiMock::new(&[
+  /// #   // Start frame, both colors and end frame, all in one transaction.
+  /// #   SpiTransaction::transaction_start(),
+  /// #   SpiTransaction::write_vec(vec![
+  /// #     0x00, 0x00, 0x00, 0x00,
+  /// #     0b11000011, 200, 255, 0,
+  /// #     0b11111100, 20, 50, 255,
+  /// #     0x00, 0x00, 0x00, 0x00,
+  /// #   ]),
+  /// #   SpiTransaction::transaction_end(),
+  /// # ]);
+  /// # use p9813::P9813;
+  /// let mut p9813 = P9813::from_raw(spi);
+  /// p9813.set_colors_alloc([(0, 255, 200), (255, 50, 20)])?;
+  /// # let mut spi = p9813.release();
+  /// # spi.done();
+  /// # Ok(())
+  /// # }
+  /// ```
+  #[cfg(feature = "alloc")]
+  pub fn set_colors_alloc(&mut self, colors: impl AsRef<[(u8, u8, u8)]>) -> Result<(), SPI::Error> {
+    let colors = colors.as_ref();
+    let mut buffer = alloc::vec::Vec::with_capacity(4 * (colors.len() + 2));
+
+    buffer.extend_from_slice(&FRAME_START);
+
+    for &(r, g, b) in colors.iter() {
+      let r = self.scale(r);
+      let g = self.scale(g);
+      let b = self.scale(b);
+
+      buffer.extend_from_slice(&color_to_array(r, g, b));
+    }
+
+    buffer.extend_from_slice(&FRAME_END);
+
+    self.spi.write(&buffer)
+  }
+}
+
+/// Error returned by [`P9813::set_colors_buffered`].
+#[derive(Debug)]
+pub enum BufferedError<E> {
+  /// The provided buffer was too small to hold the whole frame.
+  BufferTooSmall,
+  /// An error occurred while writing to the SPI peripheral.
+  Spi(E),
+}
+
+impl<E> From<E> for BufferedError<E> {
+  fn from(error: E) -> Self {
+    BufferedError::Spi(error)
+  }
 }